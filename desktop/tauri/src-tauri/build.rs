@@ -2,7 +2,193 @@
 use std::fs;
 use std::path::Path;
 
+// RFC 7396 JSON Merge Patch: recursively applies `patch` onto `target` in place.
+// A `null` in the patch removes the corresponding key; an object in both sides
+// recurses; anything else (including arrays) replaces the target value wholesale.
+fn json_merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let (Some(patch_obj), Some(target_obj)) = (patch.as_object(), target.as_object_mut()) else {
+        *target = patch.clone();
+        return;
+    };
+
+    for (key, patch_value) in patch_obj {
+        if patch_value.is_null() {
+            target_obj.remove(key);
+            continue;
+        }
+
+        match target_obj.get_mut(key) {
+            Some(existing) if existing.is_object() && patch_value.is_object() => {
+                json_merge_patch(existing, patch_value);
+            }
+            _ => {
+                target_obj.insert(key.clone(), patch_value.clone());
+            }
+        }
+    }
+}
+
+// Name of the platform-specific overlay file that gets merged into the base
+// tauri.conf.json, matching the running target OS.
+fn platform_overlay_file_name() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "tauri.macos.conf.json"
+    } else if cfg!(target_os = "windows") {
+        "tauri.windows.conf.json"
+    } else {
+        "tauri.linux.conf.json"
+    }
+}
+
+// The Tauri config can be authored in any of these formats; we probe for them
+// in priority order and round-trip in whichever one is actually present, so
+// projects that already standardized on TOML (for Cargo.toml) don't also need
+// a JSON file just for Tauri.
+#[derive(Clone, Copy)]
+enum ConfigFormat {
+    Json,
+    Json5,
+    Toml,
+}
+
+fn detect_config_file() -> Option<(std::path::PathBuf, ConfigFormat)> {
+    let candidates = [
+        ("tauri.conf.json", ConfigFormat::Json),
+        ("tauri.conf.json5", ConfigFormat::Json5),
+        ("Tauri.toml", ConfigFormat::Toml),
+    ];
+    candidates.into_iter().map(|(name, fmt)| (Path::new(name).to_path_buf(), fmt)).find(|(path, _)| path.exists())
+}
+
+fn read_config_as_json(path: &Path, format: ConfigFormat) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    match format {
+        ConfigFormat::Json => serde_json::from_str(&content).ok(),
+        ConfigFormat::Json5 => json5::from_str(&content).ok(),
+        ConfigFormat::Toml => content.parse::<toml::Value>().ok().and_then(|v| serde_json::to_value(v).ok()),
+    }
+}
+
+fn write_json_as_config(path: &Path, format: ConfigFormat, value: &serde_json::Value) -> Option<()> {
+    let serialized = match format {
+        // JSON5 is a superset of JSON, so plain pretty-printed JSON is valid JSON5.
+        ConfigFormat::Json | ConfigFormat::Json5 => serde_json::to_string_pretty(value).ok()?,
+        // Only the `version` key is ever stamped here (see the call site), so
+        // just set that one key with toml_edit (order-preserving) instead of
+        // round-tripping the whole document through serde_json — a generic
+        // JSON<->TOML converter has no faithful representation for TOML's
+        // array-of-tables (e.g. Tauri's `[[app.windows]]`), which a naive
+        // rebuild would silently drop every time this runs.
+        ConfigFormat::Toml => return stamp_toml_version(path, value.get("version")?.as_str()?),
+    };
+    fs::write(path, serialized).ok()
+}
+
+fn stamp_toml_version(path: &Path, version: &str) -> Option<()> {
+    let original = fs::read_to_string(path).ok()?;
+    let mut doc = original.parse::<toml_edit::DocumentMut>().ok()?;
+    doc.as_table_mut().insert("version", toml_edit::value(version));
+    fs::write(path, doc.to_string()).ok()
+}
+
+// Walks up from a member crate's directory looking for the workspace root
+// Cargo.toml (the first ancestor manifest that declares a `[workspace]` table).
+fn find_workspace_root(member_cargo_toml: &Path) -> Option<std::path::PathBuf> {
+    let start_dir = member_cargo_toml.parent()?.canonicalize().ok()?;
+    for ancestor in start_dir.ancestors().skip(1) {
+        let candidate = ancestor.join("Cargo.toml");
+        if let Ok(content) = fs::read_to_string(&candidate) {
+            if let Ok(value) = content.parse::<toml::Value>() {
+                if value.get("workspace").is_some() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn update_workspace_package_version(workspace_cargo_toml: &Path, version: &str) -> Option<()> {
+    // toml_edit (order-preserving), same as stamp_toml_version above — a whole
+    // -document toml::Value round-trip reorders keys and strips comments from
+    // the workspace manifest on every single build.
+    let content = fs::read_to_string(workspace_cargo_toml).ok()?;
+    let mut doc = content.parse::<toml_edit::DocumentMut>().ok()?;
+    let workspace_table = doc.as_table_mut().get_mut("workspace")?.as_table_mut()?;
+    let package = workspace_table.entry("package").or_insert(toml_edit::Item::Table(toml_edit::Table::new()));
+    package.as_table_mut()?.insert("version", toml_edit::value(version));
+    fs::write(workspace_cargo_toml, doc.to_string()).ok()
+}
+
+// Runs `git` for build provenance, degrading to "unknown" whenever git (or the
+// repo) isn't available, e.g. when building from a source tarball.
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+fn emit_build_metadata() {
+    let git_sha = git_output(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let git_dirty = git_output(&["status", "--porcelain"])
+        .map(|status| !status.is_empty())
+        .unwrap_or(false);
+
+    println!("cargo:rustc-env=QBM_GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=QBM_GIT_DIRTY={}", git_dirty);
+    println!("cargo:rustc-env=QBM_BUILD_TIMESTAMP={}", build_timestamp_utc());
+    println!("cargo:rustc-env=QBM_RUSTC_VERSION={}", rustc_version().unwrap_or_else(|| "unknown".to_string()));
+    println!("cargo:rustc-env=QBM_TARGET_TRIPLE={}", std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string()));
+
+    // Rerun when .git/HEAD changes (e.g. a branch checkout) without forcing a
+    // rebuild on every invocation the way an unconditional rerun-if-changed=.git
+    // would. A new commit on the current branch only updates the branch ref, not
+    // HEAD, so the embedded SHA and dirty flag are refreshed only when something
+    // else triggers a rerun.
+    println!("cargo:rerun-if-changed=../../../.git/HEAD");
+}
+
+fn rustc_version() -> Option<String> {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = std::process::Command::new(rustc).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+// `SystemTime` -> an RFC 3339-ish UTC timestamp without pulling in a date/time
+// crate just for the build script.
+fn build_timestamp_utc() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let days = secs / 86_400;
+    let time_of_day = secs % 86_400;
+    let (hour, minute, second) = (time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60);
+
+    // Civil-from-days algorithm (Howard Hinnant's date algorithms, public domain).
+    let z = days as i64 + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { y + 1 } else { y };
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)
+}
+
 fn main() {
+    emit_build_metadata();
+
     // Check which binaries exist for debugging
     let bin_dir = std::path::Path::new("bin");
     if bin_dir.exists() {
@@ -11,39 +197,118 @@ fn main() {
 
     // Read version from VERSION file and update both Cargo.toml and tauri.conf.json
     let version_file_path = Path::new("../../../VERSION");
-    let cargo_toml_path = Path::new("Cargo.toml");
-    let config_path = Path::new("tauri.conf.json");
+    // Resolve to an absolute path (cargo always sets CARGO_MANIFEST_DIR for build
+    // scripts) so find_workspace_root can walk ancestors from it — a bare
+    // relative "Cargo.toml" has an empty parent, which can't be canonicalized.
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let cargo_toml_path = Path::new(&manifest_dir).join("Cargo.toml");
+    let cargo_toml_path = cargo_toml_path.as_path();
+    let detected_config = detect_config_file();
+    if let Some((path, _)) = &detected_config {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
 
     if let Ok(version_content) = fs::read_to_string(version_file_path) {
-        let version = version_content.trim();
+        let raw_version = version_content.trim();
+
+        // Parse as semver so a typo'd VERSION file (e.g. "v1.2") fails the build
+        // loudly instead of silently propagating a broken version into the
+        // manifests.
+        let parsed_version = match semver::Version::parse(raw_version) {
+            Ok(v) => v,
+            Err(e) => {
+                println!("cargo:warning=Invalid VERSION file contents '{}': {}", raw_version, e);
+                panic!("VERSION file does not contain a valid semver string: '{}' ({})", raw_version, e);
+            }
+        };
+        // Write back the parsed version's string form. Non-canonical input such
+        // as leading zeros is rejected by the parse above, so this is the
+        // trimmed VERSION contents.
+        let version = parsed_version.to_string();
 
-        // Update Cargo.toml with the version
+        println!("cargo:rustc-env=QBM_SEMVER_MAJOR={}", parsed_version.major);
+        println!("cargo:rustc-env=QBM_SEMVER_MINOR={}", parsed_version.minor);
+        println!("cargo:rustc-env=QBM_SEMVER_PATCH={}", parsed_version.patch);
+
+        // Update Cargo.toml with the version, unless this member inherits its
+        // version from the workspace (`version.workspace = true`) — clobbering
+        // that would break the member and also rewrite Cargo.toml on every
+        // build, triggering needless rebuild loops.
         if let Ok(cargo_content) = fs::read_to_string(cargo_toml_path) {
-            if let Ok(mut cargo_toml) = cargo_content.parse::<toml::Value>() {
-                // Update the version field in [package] section
-                if let Some(package) = cargo_toml.get_mut("package") {
-                    if let Some(package_table) = package.as_table_mut() {
-                        package_table.insert("version".to_string(), toml::Value::String(version.to_string()));
-
-                        // Write back the updated Cargo.toml
-                        if let Ok(updated_cargo) = toml::to_string(&cargo_toml) {
-                            let _ = fs::write(cargo_toml_path, updated_cargo);
+            if let Ok(cargo_toml) = cargo_content.parse::<toml::Value>() {
+                let uses_workspace_version = cargo_toml
+                    .get("package")
+                    .and_then(|p| p.get("version"))
+                    .and_then(|v| v.as_table())
+                    .and_then(|t| t.get("workspace"))
+                    .and_then(|w| w.as_bool())
+                    .unwrap_or(false);
+
+                if uses_workspace_version {
+                    if let Some(workspace_root) = find_workspace_root(cargo_toml_path) {
+                        println!("cargo:rerun-if-changed={}", workspace_root.display());
+                        update_workspace_package_version(&workspace_root, &version);
+                    }
+                } else {
+                    let mut cargo_toml = cargo_toml;
+                    if let Some(package) = cargo_toml.get_mut("package") {
+                        if let Some(package_table) = package.as_table_mut() {
+                            package_table.insert("version".to_string(), toml::Value::String(version.to_string()));
+
+                            // Write back the updated Cargo.toml
+                            if let Ok(updated_cargo) = toml::to_string(&cargo_toml) {
+                                let _ = fs::write(cargo_toml_path, updated_cargo);
+                            }
                         }
                     }
                 }
             }
         }
 
-        // Update tauri.conf.json with the version
-        if let Ok(config_content) = fs::read_to_string(config_path) {
-            // Parse as JSON value
-            if let Ok(mut config_json) = serde_json::from_str::<serde_json::Value>(&config_content) {
-                // Update the version field
+        // Update the Tauri config (whichever format was detected) with the version
+        if let Some((config_path, config_format)) = &detected_config {
+            if let Some(mut config_json) = read_config_as_json(config_path, *config_format) {
+                // Stamp the version field in place, same as Cargo.toml above — this
+                // is the one field we do write back to the tracked source, in its
+                // original format.
                 config_json["version"] = serde_json::Value::String(version.to_string());
+                let _ = write_json_as_config(config_path, *config_format, &config_json);
 
-                // Write back the updated configuration
-                if let Ok(updated_config) = serde_json::to_string_pretty(&config_json) {
-                    let _ = fs::write(config_path, updated_config);
+                // Merge in the platform-specific overlay (tauri.macos.conf.json etc.),
+                // if present, so per-OS window titles, bundle settings, and updater
+                // endpoints can differ without duplicating the whole config. Overlays
+                // are always plain JSON regardless of the base config's format.
+                //
+                // Unlike the version bump above, the overlay is never written back
+                // over the tracked config_path — a platform overlay only applies to
+                // the build running it, so baking it into the committed source would
+                // permanently dirty the working tree (and make QBM_GIT_DIRTY always
+                // report true). Instead it's passed to rustc as the TAURI_CONFIG env
+                // var, which generate_context! applies as an RFC 7396 merge patch on
+                // top of the config it loads from disk. cargo:rustc-env does not set
+                // the variable in this build script's own process, so the
+                // tauri_build::build() call below does not see the overlay.
+                let overlay_path = Path::new(platform_overlay_file_name());
+                if overlay_path.exists() {
+                    if let Ok(overlay_content) = fs::read_to_string(overlay_path) {
+                        if let Ok(overlay_json) = serde_json::from_str::<serde_json::Value>(&overlay_content) {
+                            json_merge_patch(&mut config_json, &overlay_json);
+                        }
+                    }
+                    println!("cargo:rerun-if-changed={}", overlay_path.display());
+                }
+
+                if let Ok(merged) = serde_json::to_string(&config_json) {
+                    println!("cargo:rustc-env=TAURI_CONFIG={}", merged);
+                }
+
+                // Also drop a pretty-printed copy under OUT_DIR purely for humans
+                // inspecting a build (e.g. `cat $OUT_DIR/tauri.conf.json`); this file
+                // itself is not read by generate_context!/tauri_build.
+                if let Ok(out_dir) = std::env::var("OUT_DIR") {
+                    if let Ok(pretty) = serde_json::to_string_pretty(&config_json) {
+                        let _ = fs::write(Path::new(&out_dir).join("tauri.conf.json"), pretty);
+                    }
                 }
             }
         }
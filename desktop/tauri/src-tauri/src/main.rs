@@ -2,6 +2,8 @@
 
 use once_cell::sync::Lazy;
 use std::{
+  collections::VecDeque,
+  io::{BufRead, BufReader},
   process::{Child, Command, Stdio},
   sync::{Arc, Mutex},
   time::Duration,
@@ -10,13 +12,19 @@ use tauri::{
   AppHandle,
   Manager,
   WindowEvent,
+  WebviewUrl,
+  WebviewWindowBuilder,
   Emitter,
+  Listener,
   menu::{MenuBuilder, MenuItemBuilder, CheckMenuItemBuilder},
   tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
   RunEvent,
 };
 use tauri_plugin_single_instance::init as single_instance;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
 use tokio::time::sleep;
+use notify::Watcher;
 #[cfg(target_os = "windows")]
 use windows::{core::w, Win32::System::Registry::*};
 
@@ -28,6 +36,17 @@ const PROCESS_WAIT_TIMEOUT_MS: u64 = 200;
 const GRACEFUL_SHUTDOWN_WAIT_MS: u64 = 50;
 const POLL_INTERVAL_MS: u64 = 10;
 const HTTP_POLL_INTERVAL_MS: u64 = 250;
+const LOG_RING_BUFFER_LINES: usize = 1000;
+const SUPERVISOR_POLL_INTERVAL_MS: u64 = 1000;
+const SUPERVISOR_BACKOFF_BASE_MS: u64 = 1000;
+const SUPERVISOR_BACKOFF_MAX_MS: u64 = 30_000;
+const SUPERVISOR_STABLE_UPTIME_SECS: u64 = 30;
+const GRACEFUL_HTTP_SHUTDOWN_TIMEOUT_MS: u64 = 3000;
+const CONFIG_WATCH_DEBOUNCE_MS: u64 = 300;
+const CRASH_RECORD_STDERR_LINES: usize = 50;
+const HEALTH_CHECK_POLL_INTERVAL_MS: u64 = 5000;
+const HEALTH_CHECK_FAILURE_THRESHOLD: u32 = 3;
+const SUPERVISOR_MAX_RESTART_ATTEMPTS: u32 = 5;
 
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -36,11 +55,65 @@ static SERVER_STATE: Lazy<Arc<Mutex<Option<ServerProcess>>>> = Lazy::new(|| Arc:
 static SHOULD_EXIT: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
 static MINIMIZE_TO_TRAY: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
 static STARTUP_ENABLED: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
+static AUTO_RESTART_ON_CONFIG_CHANGE: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
+static LOG_BUFFER: Lazy<Arc<Mutex<VecDeque<ServerLogLine>>>> = Lazy::new(|| Arc::new(Mutex::new(VecDeque::with_capacity(LOG_RING_BUFFER_LINES))));
+static SERVER_RUNTIME_STATE: Lazy<Arc<Mutex<ServerState>>> = Lazy::new(|| Arc::new(Mutex::new(ServerState::Starting)));
+static BASE_TRAY_ICON: Lazy<Mutex<Option<tauri::image::Image<'static>>>> = Lazy::new(|| Mutex::new(None));
+// Tracks whether the crash dialog has already been shown for the current
+// crash-loop episode, so a backend stuck restarting with backoff doesn't stack
+// a new modal (and crash file) on every single attempt.
+static CRASH_DIALOG_SHOWN_THIS_EPISODE: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ServerLogLine {
+  stream: &'static str,
+  line: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "state")]
+enum ServerState {
+  Starting,
+  Running,
+  Restarting { attempt: u32 },
+  Stopped,
+  Failed,
+}
+
+impl ServerState {
+  fn status_text(&self) -> String {
+    match self {
+      ServerState::Starting => "Status: Starting".to_string(),
+      ServerState::Running => "Status: Running".to_string(),
+      ServerState::Restarting { attempt } => format!("Status: Restarting (attempt {})", attempt),
+      ServerState::Stopped => "Status: Stopped".to_string(),
+      ServerState::Failed => "Status: Failed".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ServerExitInfo {
+  code: Option<i32>,
+  signal: Option<i32>,
+  unexpected: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct CrashRecord {
+  timestamp_secs: u64,
+  code: Option<i32>,
+  signal: Option<i32>,
+  stderr_tail: Vec<String>,
+}
 
 struct ServerProcess {
   child: Child,
   #[cfg(all(windows, feature = "winjob"))]
   job: Option<windows::Win32::Foundation::HANDLE>,
+  #[cfg(unix)]
+  pgid: i32,
+  args: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +184,36 @@ fn save_minimize_setting(app: &AppHandle, value: bool) {
   }
 }
 
+fn load_auto_restart_setting(app: &AppHandle) -> bool {
+  match app.path().app_data_dir() {
+    Ok(data_dir) => {
+      let file = data_dir.join("auto_restart_on_config_change.txt");
+      if file.exists() {
+        std::fs::read_to_string(&file).map(|content| content.trim() == "true").unwrap_or(false)
+      } else {
+        false
+      }
+    }
+    Err(_) => false,
+  }
+}
+
+fn save_auto_restart_setting(app: &AppHandle, value: bool) {
+  if let Ok(data_dir) = app.path().app_data_dir() {
+    if let Err(e) = std::fs::create_dir_all(&data_dir) {
+      eprintln!("Failed to create app data directory: {}", e);
+      return;
+    }
+
+    let file = data_dir.join("auto_restart_on_config_change.txt");
+    if let Err(e) = std::fs::write(&file, if value { "true" } else { "false" }) {
+      eprintln!("Failed to save auto-restart setting to {:?}: {}", file, e);
+    }
+  } else {
+    eprintln!("Failed to get app data directory");
+  }
+}
+
 #[cfg(target_os = "windows")]
 fn is_startup_enabled() -> bool {
   unsafe {
@@ -333,6 +436,36 @@ fn parse_host_from_args(args: &[String]) -> String {
   "127.0.0.1".to_string()
 }
 
+fn parse_config_dir_from_args(args: &[String]) -> Option<std::path::PathBuf> {
+  // CLI args take precedence
+  let mut i = 0;
+  while i < args.len() {
+    let arg = &args[i];
+    if arg == "--config-dir" || arg == "-c" {
+      if i + 1 < args.len() {
+        let s = args[i + 1].trim();
+        if !s.is_empty() {
+          return Some(std::path::PathBuf::from(s));
+        }
+      }
+    } else if let Some(rest) = arg.strip_prefix("--config-dir=") {
+      let s = rest.trim();
+      if !s.is_empty() {
+        return Some(std::path::PathBuf::from(s));
+      }
+    }
+    i += 1;
+  }
+  // Fallback to environment variable
+  if let Ok(val) = std::env::var("QBT_CONFIG_DIR") {
+    let s = val.trim();
+    if !s.is_empty() {
+      return Some(std::path::PathBuf::from(s));
+    }
+  }
+  None
+}
+
 fn build_server_url_effective(args: &[String]) -> String {
   let host = parse_host_from_args(args);
   let port = parse_port_from_args(args);
@@ -342,20 +475,260 @@ fn build_server_url_effective(args: &[String]) -> String {
   }
 }
 
-fn build_tray_menu<R: tauri::Runtime, M: tauri::Manager<R>>(app: &M, minimize_to_tray: bool, startup_enabled: bool) -> Result<tauri::menu::Menu<R>, tauri::Error> {
-  let open_item = MenuItemBuilder::with_id("open", "Open").build(app)?;
-  let restart_item = MenuItemBuilder::with_id("restart", "Restart Server").build(app)?;
+fn build_tray_menu<R: tauri::Runtime, M: tauri::Manager<R>>(app: &M, minimize_to_tray: bool, startup_enabled: bool, auto_restart_on_config_change: bool, state: ServerState, window_visible: bool) -> Result<tauri::menu::Menu<R>, tauri::Error> {
+  let is_stopped = matches!(state, ServerState::Stopped | ServerState::Failed);
+
+  let status_item = MenuItemBuilder::with_id("status", state.status_text()).enabled(false).build(app)?;
+  let toggle_label = if window_visible { "Hide" } else { "Show" };
+  let toggle_item = MenuItemBuilder::with_id("toggle_window", toggle_label).build(app)?;
+  let start_item = MenuItemBuilder::with_id("start_server", "Start Server").enabled(is_stopped).build(app)?;
+  let stop_item = MenuItemBuilder::with_id("stop_server", "Stop Server").enabled(!is_stopped).build(app)?;
+  let restart_item = MenuItemBuilder::with_id("restart_server", "Restart Server").enabled(!is_stopped).build(app)?;
+  let logs_item = MenuItemBuilder::with_id("logs", "Logs").build(app)?;
   let minimize_item = CheckMenuItemBuilder::with_id("minimize_startup", "Minimize to Tray on Startup")
     .checked(minimize_to_tray)
     .build(app)?;
   let startup_item = CheckMenuItemBuilder::with_id("startup", "Start on System Startup")
     .checked(startup_enabled)
     .build(app)?;
+  let auto_restart_item = CheckMenuItemBuilder::with_id("auto_restart_config", "Auto-restart on Config Change")
+    .checked(auto_restart_on_config_change)
+    .build(app)?;
   let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
 
   MenuBuilder::new(app)
-    .items(&[&open_item, &restart_item, &minimize_item, &startup_item, &quit_item])
+    .items(&[&status_item, &toggle_item, &start_item, &stop_item, &restart_item, &logs_item, &minimize_item, &startup_item, &auto_restart_item, &quit_item])
+    .build()
+}
+
+// Reads whether the "main" window is currently visible, defaulting to visible
+// if the window can't be queried (keeps the tray item as "Hide" rather than
+// guessing "Show" when we're unsure).
+fn is_main_window_visible(app: &AppHandle) -> bool {
+  app.get_webview_window("main").map(|w| w.is_visible().unwrap_or(true)).unwrap_or(true)
+}
+
+// Rebuilds the tray menu from the current shared state and applies it to the
+// "main" tray icon. Centralizes the read-state -> build -> set_menu dance that
+// every settings toggle and the supervisor both need.
+fn refresh_tray_menu(app: &AppHandle) {
+  let minimize_to_tray = *MINIMIZE_TO_TRAY.lock().unwrap();
+  let startup_enabled = *STARTUP_ENABLED.lock().unwrap();
+  let auto_restart_on_config_change = *AUTO_RESTART_ON_CONFIG_CHANGE.lock().unwrap();
+  let state = *SERVER_RUNTIME_STATE.lock().unwrap();
+  let window_visible = is_main_window_visible(app);
+
+  match build_tray_menu(app, minimize_to_tray, startup_enabled, auto_restart_on_config_change, state, window_visible) {
+    Ok(tray_menu) => {
+      if let Some(tray_icon) = app.tray_by_id("main") {
+        if let Err(e) = tray_icon.set_menu(Some(tray_menu)) {
+          eprintln!("Failed to update tray menu: {}", e);
+        }
+        update_tray_icon(app, &tray_icon);
+      } else {
+        eprintln!("Could not find tray icon to update menu");
+      }
+    }
+    Err(e) => eprintln!("Failed to build tray menu: {}", e),
+  }
+}
+
+// Swaps the tray icon between the app's full-color icon (server healthy/running)
+// and a greyed-out derivative (starting/restarting/stopped/failed) so users can
+// tell backend health at a glance without opening the menu.
+fn update_tray_icon(app: &AppHandle, tray_icon: &tauri::tray::TrayIcon) {
+  let mut base_guard = BASE_TRAY_ICON.lock().unwrap();
+  if base_guard.is_none() {
+    *base_guard = app.default_window_icon().cloned();
+  }
+  let Some(base) = base_guard.clone() else { return };
+  drop(base_guard);
+
+  let state = *SERVER_RUNTIME_STATE.lock().unwrap();
+  let icon = if state == ServerState::Running { base } else { greyscale_icon(&base) };
+  let _ = tray_icon.set_icon(Some(icon));
+}
+
+fn greyscale_icon(icon: &tauri::image::Image<'static>) -> tauri::image::Image<'static> {
+  let mut pixels = Vec::with_capacity(icon.rgba().len());
+  for px in icon.rgba().chunks_exact(4) {
+    let luma = (0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32) as u8;
+    pixels.extend_from_slice(&[luma, luma, luma, px[3]]);
+  }
+  tauri::image::Image::new_owned(pixels, icon.width(), icon.height())
+}
+
+fn set_server_state(app: &AppHandle, state: ServerState) {
+  *SERVER_RUNTIME_STATE.lock().unwrap() = state;
+  let _ = app.emit("server-state", state);
+  refresh_tray_menu(app);
+
+  // A successful start or an explicit stop ends the current failure episode,
+  // so the next unexpected exit is treated as a fresh crash again.
+  if matches!(state, ServerState::Running | ServerState::Stopped) {
+    *CRASH_DIALOG_SHOWN_THIS_EPISODE.lock().unwrap() = false;
+  }
+}
+
+fn push_log_line(app: &AppHandle, stream: &'static str, line: String) {
+  let entry = ServerLogLine { stream, line };
+  {
+    let mut buffer = LOG_BUFFER.lock().unwrap();
+    if buffer.len() >= LOG_RING_BUFFER_LINES {
+      buffer.pop_front();
+    }
+    buffer.push_back(entry.clone());
+  }
+  let _ = app.emit("server-log", entry);
+}
+
+fn spawn_log_readers(app: &AppHandle, child: &mut Child) {
+  if let Some(stdout) = child.stdout.take() {
+    let app = app.clone();
+    std::thread::spawn(move || {
+      let reader = BufReader::new(stdout);
+      for line in reader.lines() {
+        match line {
+          Ok(line) => push_log_line(&app, "stdout", line),
+          Err(_) => break,
+        }
+      }
+    });
+  }
+
+  if let Some(stderr) = child.stderr.take() {
+    let app = app.clone();
+    std::thread::spawn(move || {
+      let reader = BufReader::new(stderr);
+      for line in reader.lines() {
+        match line {
+          Ok(line) => push_log_line(&app, "stderr", line),
+          Err(_) => break,
+        }
+      }
+    });
+  }
+}
+
+// Classifies a terminated child's exit status, emits it as a "server-exit" event, and
+// for unexpected crashes writes a crash record and offers to open the logs.
+fn report_server_exit(app: &AppHandle, status: std::process::ExitStatus, unexpected: bool) {
+  let code = status.code();
+  #[cfg(unix)]
+  let signal = {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+  };
+  #[cfg(not(unix))]
+  let signal: Option<i32> = None;
+
+  let info = ServerExitInfo { code, signal, unexpected };
+  println!("Server exited: code={:?} signal={:?} unexpected={}", code, signal, unexpected);
+  let _ = app.emit("server-exit", info.clone());
+
+  if !unexpected {
+    return;
+  }
+
+  let crash_file = write_crash_record(app, &info);
+
+  // Still record every crash, but only pop the modal once per failure episode —
+  // otherwise a crash-looping backend stacks a new dialog on every backoff
+  // restart attempt.
+  let mut shown_guard = CRASH_DIALOG_SHOWN_THIS_EPISODE.lock().unwrap();
+  if *shown_guard {
+    return;
+  }
+  *shown_guard = true;
+  drop(shown_guard);
+
+  let app_dialog = app.clone();
+  app.dialog()
+    .message("The qbit-manage server exited unexpectedly. Would you like to open the logs?")
+    .title("qbit-manage crashed")
+    .kind(MessageDialogKind::Error)
+    .buttons(MessageDialogButtons::OkCancelCustom("Open Logs".into(), "Dismiss".into()))
+    .show(move |opened_logs| {
+      if opened_logs {
+        open_logs_window(&app_dialog);
+      } else if let Some(path) = &crash_file {
+        let _ = app_dialog.opener().reveal_item_in_dir(path);
+      }
+    });
+}
+
+fn write_crash_record(app: &AppHandle, info: &ServerExitInfo) -> Option<std::path::PathBuf> {
+  let data_dir = app.path().app_data_dir().ok()?;
+  let crash_dir = data_dir.join("crash_logs");
+  std::fs::create_dir_all(&crash_dir).ok()?;
+
+  let timestamp_secs = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or(0);
+
+  let stderr_tail: Vec<String> = LOG_BUFFER
+    .lock()
+    .unwrap()
+    .iter()
+    .filter(|entry| entry.stream == "stderr")
+    .rev()
+    .take(CRASH_RECORD_STDERR_LINES)
+    .map(|entry| entry.line.clone())
+    .rev()
+    .collect();
+
+  let record = CrashRecord {
+    timestamp_secs,
+    code: info.code,
+    signal: info.signal,
+    stderr_tail,
+  };
+
+  let file = crash_dir.join(format!("crash_{}.json", timestamp_secs));
+  match serde_json::to_string_pretty(&record) {
+    Ok(json) => {
+      if let Err(e) = std::fs::write(&file, json) {
+        eprintln!("Failed to write crash record to {:?}: {}", file, e);
+        return None;
+      }
+      Some(file)
+    }
+    Err(e) => {
+      eprintln!("Failed to serialize crash record: {}", e);
+      None
+    }
+  }
+}
+
+fn open_logs_window(app: &AppHandle) {
+  if let Some(win) = app.get_webview_window("logs") {
+    let _ = win.show();
+    let _ = win.set_focus();
+    let backlog: Vec<ServerLogLine> = LOG_BUFFER.lock().unwrap().iter().cloned().collect();
+    let _ = app.emit("server-log-backlog", backlog);
+    return;
+  }
+
+  // Served as a bundled app asset (not an External data: URL) so the window
+  // actually gets the injected `window.__TAURI__` event API — Tauri only
+  // injects IPC into webviews loaded from the app's own origin.
+  if let Ok(win) = WebviewWindowBuilder::new(app, "logs", WebviewUrl::App(std::path::PathBuf::from("logs.html")))
+    .title("qbit-manage Server Logs")
+    .inner_size(720.0, 480.0)
     .build()
+  {
+    let _ = win.set_focus();
+
+    // logs.html emits "logs-ready" once its own `listen` handlers are
+    // registered; emitting the backlog right after build() races that and
+    // Tauri drops events with no listener attached yet, so wait to be asked.
+    let win_for_backlog = win.clone();
+    win.once("logs-ready", move |_event| {
+      let backlog: Vec<ServerLogLine> = LOG_BUFFER.lock().unwrap().iter().cloned().collect();
+      let _ = win_for_backlog.emit("server-log-backlog", backlog);
+    });
+  }
 }
 
 fn get_binary_names() -> Vec<&'static str> {
@@ -412,11 +785,28 @@ fn resolve_server_binary(app: &AppHandle) -> Option<std::path::PathBuf> {
   find_binary_in_paths(&search_paths, &bin_names)
 }
 
-fn stop_server() {
+fn stop_server(app: &AppHandle) {
   if let Some(server_process) = SERVER_STATE.lock().unwrap().take() {
     let mut child = server_process.child;
+    let args = server_process.args;
+    #[cfg(windows)]
     let pid = child.id();
 
+    // Ask the server to shut down through its own HTTP endpoint first, so an
+    // in-progress qbit-manage run (partial tag/category updates) isn't interrupted
+    // mid-write. Only fall back to the forced OS-level termination below if it
+    // doesn't exit within the bounded timeout.
+    if request_graceful_shutdown(&args) {
+      let deadline = std::time::Instant::now() + Duration::from_millis(GRACEFUL_HTTP_SHUTDOWN_TIMEOUT_MS);
+      while std::time::Instant::now() < deadline {
+        if let Ok(Some(status)) = child.try_wait() {
+          report_server_exit(app, status, false);
+          return;
+        }
+        std::thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+      }
+    }
+
     // On Windows, use immediate process tree termination for faster cleanup
     #[cfg(all(windows, feature = "winjob"))]
     {
@@ -435,14 +825,16 @@ fn stop_server() {
       terminate_process_tree_windows(pid);
     }
 
-    // On Unix, try graceful shutdown first but with minimal delay
+    // On Unix, signal the whole process group (not just the immediate child) so
+    // any workers qbit-manage forked for a scheduled run are cleaned up too.
     #[cfg(unix)]
     {
-      unsafe { libc::kill(pid as i32, libc::SIGTERM); }
+      let pgid = server_process.pgid;
+      unsafe { libc::kill(-pgid, libc::SIGTERM); }
       // Very brief wait for graceful shutdown
       std::thread::sleep(Duration::from_millis(GRACEFUL_SHUTDOWN_WAIT_MS));
       if child.try_wait().ok().flatten().is_none() {
-        let _ = child.kill();
+        unsafe { libc::kill(-pgid, libc::SIGKILL); }
       }
     }
 
@@ -451,7 +843,9 @@ fn stop_server() {
 
     // Force kill if still running
     let _ = child.kill();
-    let _ = child.wait();
+    if let Ok(status) = child.wait() {
+      report_server_exit(app, status, false);
+    }
   }
 }
 
@@ -495,8 +889,9 @@ fn cleanup_and_exit_with_app(app: &AppHandle) {
 
   // Do cleanup and exit in background thread so UI doesn't freeze
   // The tray will disappear when the process exits
-  std::thread::spawn(|| {
-    stop_server();
+  let app = app.clone();
+  std::thread::spawn(move || {
+    stop_server(&app);
     std::process::exit(0);
   });
 }
@@ -522,6 +917,59 @@ async fn wait_until_ready(args: &[String], timeout: Duration) -> bool {
   false
 }
 
+// Single-shot health probe (no retry loop, unlike wait_until_ready) used by the
+// health-check supervisor to tell a hung-but-still-running process apart from
+// one that's simply still starting up.
+async fn is_server_responsive(args: &[String]) -> bool {
+  let client = match reqwest::Client::builder()
+    .danger_accept_invalid_certs(true)
+    .timeout(Duration::from_secs(2))
+    .build()
+  {
+    Ok(client) => client,
+    Err(_) => return false,
+  };
+
+  let url = build_server_url_effective(args);
+  matches!(client.get(&url).send().await, Ok(resp) if resp.status().as_u16() < 500)
+}
+
+// Issues the graceful-shutdown request to the server's control endpoint and
+// reports whether it was accepted. Blocking: stop_server is run on a
+// std::thread background thread everywhere except RunEvent::Exit, which calls
+// it directly on the event-loop thread.
+fn request_graceful_shutdown(args: &[String]) -> bool {
+  tauri::async_runtime::block_on(async {
+    let client = match reqwest::Client::builder()
+      .danger_accept_invalid_certs(true)
+      .timeout(Duration::from_secs(2))
+      .build()
+    {
+      Ok(client) => client,
+      Err(_) => return false,
+    };
+
+    let url = format!("{}/api/shutdown", build_server_url_effective(args));
+    // A non-success response (e.g. 404 because the backend has no shutdown
+    // endpoint) is not an acknowledgement — treating it as one makes stop_server
+    // block for the full graceful-shutdown timeout waiting for an exit that
+    // will never come.
+    matches!(client.post(&url).send().await, Ok(resp) if resp.status().is_success())
+  })
+}
+
+// On macOS, hiding the window via CloseRequested still leaves a dock icon behind,
+// so the app looks "open" even while minimized to tray. Flip the activation policy
+// alongside every show/hide so the dock icon tracks actual window visibility.
+#[cfg(target_os = "macos")]
+fn set_dock_visible(app: &AppHandle, visible: bool) {
+  let policy = if visible { tauri::ActivationPolicy::Regular } else { tauri::ActivationPolicy::Accessory };
+  let _ = app.set_activation_policy(policy);
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_dock_visible(_app: &AppHandle, _visible: bool) {}
+
 fn open_app_window(app: &AppHandle) {
   // Check if minimize to tray is enabled and respect it
   let minimize_to_tray = match MINIMIZE_TO_TRAY.lock() {
@@ -537,6 +985,7 @@ fn open_app_window(app: &AppHandle) {
     if let Some(win) = app.get_webview_window("main") {
       let _ = win.show();
       let _ = win.set_focus();
+      set_dock_visible(app, true);
     }
   }
 }
@@ -546,6 +995,7 @@ fn force_open_app_window(app: &AppHandle) {
   if let Some(win) = app.get_webview_window("main") {
     let _ = win.show();
     let _ = win.set_focus();
+    set_dock_visible(app, true);
   }
 }
 
@@ -667,7 +1117,24 @@ fn start_server(app: &AppHandle, cfg: &AppConfig) -> tauri::Result<()> {
     cmd.creation_flags(CREATE_NO_WINDOW);
   }
 
-  let child = cmd.spawn()?;
+  // On Unix, put the child in its own new process group so stop_server can signal
+  // the whole tree (qbit-manage's scheduled-run workers included) instead of just
+  // the immediate PID. setpgid(0, 0) inside the child makes its pgid equal its pid.
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::CommandExt;
+    unsafe {
+      cmd.pre_exec(|| {
+        libc::setpgid(0, 0);
+        Ok(())
+      });
+    }
+  }
+
+  let mut child = cmd.spawn()?;
+  spawn_log_readers(app, &mut child);
+  #[cfg(unix)]
+  let pgid = child.id() as i32;
 
   // Add process to job object on Windows
   #[cfg(all(windows, feature = "winjob"))]
@@ -696,27 +1163,210 @@ fn start_server(app: &AppHandle, cfg: &AppConfig) -> tauri::Result<()> {
     child,
     #[cfg(all(windows, feature = "winjob"))]
     job,
+    #[cfg(unix)]
+    pgid,
+    args: cfg.args.clone(),
   });
   Ok(())
 }
 
+// Periodically checks whether the tracked child is still alive, and also
+// (while it's supposedly Running) probes its HTTP endpoint every
+// HEALTH_CHECK_POLL_INTERVAL_MS so a backend that's still alive but wedged
+// (deadlocked, stuck behind a bad migration, etc.) gets caught too — a plain
+// try_wait only notices a crash once the process has actually exited. Both
+// triggers restart through the same attempt counter/backoff/cap so they can't
+// fight each other over SERVER_STATE; after SUPERVISOR_MAX_RESTART_ATTEMPTS
+// the supervisor gives up for good (ServerState::Failed) instead of retrying
+// forever. Backoff and the attempt counter reset after the server has stayed
+// up for SUPERVISOR_STABLE_UPTIME_SECS.
+fn spawn_supervisor(app: AppHandle, cfg: AppConfig) {
+  std::thread::spawn(move || {
+    let mut backoff_ms = SUPERVISOR_BACKOFF_BASE_MS;
+    let mut restart_attempt: u32 = 0;
+    let mut running_since = std::time::Instant::now();
+    let mut consecutive_health_failures: u32 = 0;
+    let mut last_health_check = std::time::Instant::now();
+
+    loop {
+      std::thread::sleep(Duration::from_millis(SUPERVISOR_POLL_INTERVAL_MS));
+
+      if *SHOULD_EXIT.lock().unwrap() {
+        break;
+      }
+
+      let exit_status = {
+        let mut guard = SERVER_STATE.lock().unwrap();
+        match guard.as_mut() {
+          Some(server_process) => match server_process.child.try_wait() {
+            Ok(Some(status)) => {
+              *guard = None;
+              Some(status)
+            }
+            _ => None,
+          },
+          None => None,
+        }
+      };
+
+      let needs_restart = if let Some(status) = exit_status {
+        report_server_exit(&app, status, true);
+        true
+      } else if *SERVER_RUNTIME_STATE.lock().unwrap() == ServerState::Running
+        && last_health_check.elapsed() >= Duration::from_millis(HEALTH_CHECK_POLL_INTERVAL_MS)
+      {
+        last_health_check = std::time::Instant::now();
+        if tauri::async_runtime::block_on(is_server_responsive(&cfg.args)) {
+          consecutive_health_failures = 0;
+          false
+        } else {
+          consecutive_health_failures += 1;
+          if consecutive_health_failures >= HEALTH_CHECK_FAILURE_THRESHOLD {
+            consecutive_health_failures = 0;
+            eprintln!("Backend unresponsive to health checks, forcing restart");
+            stop_server(&app);
+            true
+          } else {
+            false
+          }
+        }
+      } else {
+        false
+      };
+
+      if !needs_restart {
+        if running_since.elapsed() >= Duration::from_secs(SUPERVISOR_STABLE_UPTIME_SECS) {
+          backoff_ms = SUPERVISOR_BACKOFF_BASE_MS;
+          restart_attempt = 0;
+        }
+        continue;
+      }
+
+      if *SHOULD_EXIT.lock().unwrap() {
+        break;
+      }
+
+      restart_attempt += 1;
+      if restart_attempt > SUPERVISOR_MAX_RESTART_ATTEMPTS {
+        eprintln!("Backend failed {} restart attempts, giving up", SUPERVISOR_MAX_RESTART_ATTEMPTS);
+        set_server_state(&app, ServerState::Failed);
+        break;
+      }
+      set_server_state(&app, ServerState::Restarting { attempt: restart_attempt });
+
+      std::thread::sleep(Duration::from_millis(backoff_ms));
+      backoff_ms = (backoff_ms * 2).min(SUPERVISOR_BACKOFF_MAX_MS);
+
+      if start_server(&app, &cfg).is_ok() {
+        running_since = std::time::Instant::now();
+        let app_ready = app.clone();
+        let cfg_ready = cfg.clone();
+        tauri::async_runtime::spawn(async move {
+          if wait_until_ready(&cfg_ready.args, Duration::from_secs(SERVER_RESTART_TIMEOUT_SECS)).await {
+            set_server_state(&app_ready, ServerState::Running);
+            redirect_to_server(&app_ready, &cfg_ready);
+          } else {
+            set_server_state(&app_ready, ServerState::Failed);
+          }
+        });
+      } else {
+        set_server_state(&app, ServerState::Failed);
+      }
+    }
+  });
+}
+
+// Watches the resolved qbit-manage config directory and, on a debounced change,
+// emits "config-changed" and optionally restarts the server (mirroring the
+// "restart" tray handler) if the user has enabled auto-restart.
+fn spawn_config_watcher(app: AppHandle, cfg: AppConfig) {
+  let Some(config_dir) = parse_config_dir_from_args(&cfg.args) else {
+    return;
+  };
+  if !config_dir.exists() {
+    return;
+  }
+
+  let (tx, rx) = std::sync::mpsc::channel();
+  let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    if let Ok(event) = res {
+      let _ = tx.send(event);
+    }
+  }) {
+    Ok(watcher) => watcher,
+    Err(e) => {
+      eprintln!("Failed to create config watcher: {}", e);
+      return;
+    }
+  };
+
+  if let Err(e) = watcher.watch(&config_dir, notify::RecursiveMode::NonRecursive) {
+    eprintln!("Failed to watch config dir {:?}: {}", config_dir, e);
+    return;
+  }
+
+  std::thread::spawn(move || {
+    // Keep the watcher alive for as long as this thread runs.
+    let _watcher = watcher;
+
+    while rx.recv().is_ok() {
+      // Coalesce a burst of events (e.g. an editor's save-as-temp-then-rename)
+      // into a single restart instead of thrashing.
+      while rx.recv_timeout(Duration::from_millis(CONFIG_WATCH_DEBOUNCE_MS)).is_ok() {}
+
+      if *SHOULD_EXIT.lock().unwrap() {
+        break;
+      }
+
+      let _ = app.emit("config-changed", ());
+
+      if !*AUTO_RESTART_ON_CONFIG_CHANGE.lock().unwrap() {
+        continue;
+      }
 
+      let cfg = cfg.clone();
+      let app_handle = app.clone();
+      std::thread::spawn(move || {
+        // Drive the same Starting/Running/Failed transitions as the tray
+        // "restart_server" handler, so the tray status item/icon don't stay
+        // stuck on "Running" through this stop/start cycle and race the
+        // supervisor's own health probe.
+        stop_server(&app_handle);
+        std::thread::sleep(Duration::from_millis(PROCESS_WAIT_TIMEOUT_MS));
+        set_server_state(&app_handle, ServerState::Starting);
+        if start_server(&app_handle, &cfg).is_ok() {
+          tauri::async_runtime::spawn(async move {
+            if wait_until_ready(&cfg.args, Duration::from_secs(SERVER_RESTART_TIMEOUT_SECS)).await {
+              set_server_state(&app_handle, ServerState::Running);
+              redirect_to_server(&app_handle, &cfg);
+            } else {
+              set_server_state(&app_handle, ServerState::Failed);
+            }
+          });
+        } else {
+          set_server_state(&app_handle, ServerState::Failed);
+        }
+      });
+    }
+  });
+}
 
 pub fn run() {
   tauri::Builder::default()
     // Single instance should be first (per docs)
     .plugin(single_instance(|app, _argv, _cwd| {
-      // Load the minimize setting directly in case it hasn't been loaded yet
-      let minimize_to_tray = load_minimize_setting(app);
-      if !minimize_to_tray {
-        if let Some(win) = app.get_webview_window("main") {
-          let _ = win.show();
-          let _ = win.set_focus();
-        }
-      }
+      // A second launch landed here instead of spawning its own server/window;
+      // just bring the existing instance forward. Redirecting to
+      // build_server_url_effective(&second_launch_argv) would use the *new*
+      // process's args, not the ones the already-running server/window were
+      // actually started with, so a re-launch with no args would send the
+      // live window to the default port even if the running server is on a
+      // different one.
+      force_open_app_window(app);
     }))
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_opener::init())
+    .plugin(tauri_plugin_dialog::init())
     .setup(|app| {
       let app_handle = app.handle().clone();
 
@@ -727,8 +1377,11 @@ pub fn run() {
       let startup_enabled = is_startup_enabled();
       *STARTUP_ENABLED.lock().unwrap() = startup_enabled;
 
+      let auto_restart_on_config_change = load_auto_restart_setting(&app_handle);
+      *AUTO_RESTART_ON_CONFIG_CHANGE.lock().unwrap() = auto_restart_on_config_change;
+
       // Build tray menu (v2 API)
-      let tray_menu = build_tray_menu(app, minimize_to_tray, startup_enabled)?;
+      let tray_menu = build_tray_menu(app, minimize_to_tray, startup_enabled, auto_restart_on_config_change, ServerState::Starting, !minimize_to_tray)?;
 
       // Create tray icon with explicit icon and ID
       let _tray_icon = TrayIconBuilder::with_id("main")
@@ -741,31 +1394,88 @@ pub fn run() {
             ..
           } = event {
             let app = tray.app_handle();
-            force_open_app_window(app);
+            if is_main_window_visible(app) {
+              if let Some(win) = app.get_webview_window("main") {
+                let _ = win.hide();
+                if *MINIMIZE_TO_TRAY.lock().unwrap() {
+                  set_dock_visible(app, false);
+                }
+              }
+            } else {
+              force_open_app_window(app);
+            }
+            refresh_tray_menu(app);
           }
         })
         .on_menu_event(|app, event| {
           match event.id().as_ref() {
-            "open" => {
-              force_open_app_window(app);
+            "toggle_window" => {
+              if is_main_window_visible(app) {
+                if let Some(win) = app.get_webview_window("main") {
+                  let _ = win.hide();
+                  if *MINIMIZE_TO_TRAY.lock().unwrap() {
+                    set_dock_visible(app, false);
+                  }
+                }
+              } else {
+                force_open_app_window(app);
+              }
+              refresh_tray_menu(app);
             }
-            "restart" => {
-              // Stop server first, then start it again with minimal delay
-              stop_server();
-
+            "logs" => {
+              open_logs_window(app);
+            }
+            "start_server" => {
+              // Bring the backend back up without touching the existing process state
+              // (start_server is already a no-op if something is running).
+              let cfg = app_config(app);
+              let app_handle_start = app.clone();
+              set_server_state(app, ServerState::Starting);
+              std::thread::spawn(move || {
+                if start_server(&app_handle_start, &cfg).is_ok() {
+                  tauri::async_runtime::spawn(async move {
+                    if wait_until_ready(&cfg.args, Duration::from_secs(SERVER_RESTART_TIMEOUT_SECS)).await {
+                      set_server_state(&app_handle_start, ServerState::Running);
+                      redirect_to_server(&app_handle_start, &cfg);
+                    } else {
+                      set_server_state(&app_handle_start, ServerState::Failed);
+                    }
+                  });
+                } else {
+                  set_server_state(&app_handle_start, ServerState::Failed);
+                }
+              });
+            }
+            "stop_server" => {
+              // Halt the backend without exiting the tray app or the desktop process.
+              let app = app.clone();
+              std::thread::spawn(move || {
+                stop_server(&app);
+                set_server_state(&app, ServerState::Stopped);
+              });
+            }
+            "restart_server" => {
               let cfg = app_config(app);
               let app_handle_restart = app.clone();
 
-              // Start server in a separate thread to avoid blocking the UI
+              // Stop (now possibly blocking on the graceful HTTP shutdown) and start
+              // again in a background thread so the UI doesn't freeze.
               std::thread::spawn(move || {
+                stop_server(&app_handle_restart);
                 // Brief delay to ensure process cleanup
                 std::thread::sleep(Duration::from_millis(PROCESS_WAIT_TIMEOUT_MS));
+                set_server_state(&app_handle_restart, ServerState::Starting);
                 if start_server(&app_handle_restart, &cfg).is_ok() {
                   tauri::async_runtime::spawn(async move {
                     if wait_until_ready(&cfg.args, Duration::from_secs(SERVER_RESTART_TIMEOUT_SECS)).await {
+                      set_server_state(&app_handle_restart, ServerState::Running);
                       redirect_to_server(&app_handle_restart, &cfg);
+                    } else {
+                      set_server_state(&app_handle_restart, ServerState::Failed);
                     }
                   });
+                } else {
+                  set_server_state(&app_handle_restart, ServerState::Failed);
                 }
               });
             }
@@ -778,24 +1488,14 @@ pub fn run() {
               save_minimize_setting(app, new_value);
               println!("Toggled minimize to tray setting to: {}", new_value);
 
-              // Rebuild menu with updated checked state
-              let startup_enabled = *STARTUP_ENABLED.lock().unwrap();
-
-              if let Ok(tray_menu) = build_tray_menu(app, new_value, startup_enabled) {
-                // Get all tray icons and update them
-                let tray_icons = app.tray_by_id("main");
-                if let Some(tray_icon) = tray_icons {
-                  if let Err(e) = tray_icon.set_menu(Some(tray_menu)) {
-                    eprintln!("Failed to update tray menu: {}", e);
-                  } else {
-                    println!("Successfully updated tray menu");
-                  }
-                } else {
-                  eprintln!("Could not find tray icon to update menu");
-                }
-              } else {
-                eprintln!("Failed to build tray menu");
+              let window_hidden = app.get_webview_window("main").map(|w| !w.is_visible().unwrap_or(true)).unwrap_or(false);
+              if new_value && window_hidden {
+                set_dock_visible(app, false);
+              } else if !new_value {
+                set_dock_visible(app, true);
               }
+
+              refresh_tray_menu(app);
             }
             "startup" => {
               let mut current = STARTUP_ENABLED.lock().unwrap();
@@ -806,24 +1506,18 @@ pub fn run() {
               set_startup_enabled(new_value);
               println!("Toggled startup setting to: {}", new_value);
 
-              // Rebuild menu with updated checked state
-              let minimize_to_tray = *MINIMIZE_TO_TRAY.lock().unwrap();
-
-              if let Ok(tray_menu) = build_tray_menu(app, minimize_to_tray, new_value) {
-                // Get all tray icons and update them
-                let tray_icons = app.tray_by_id("main");
-                if let Some(tray_icon) = tray_icons {
-                  if let Err(e) = tray_icon.set_menu(Some(tray_menu)) {
-                    eprintln!("Failed to update tray menu: {}", e);
-                  } else {
-                    println!("Successfully updated tray menu");
-                  }
-                } else {
-                  eprintln!("Could not find tray icon to update menu");
-                }
-              } else {
-                eprintln!("Failed to build tray menu");
-              }
+              refresh_tray_menu(app);
+            }
+            "auto_restart_config" => {
+              let mut current = AUTO_RESTART_ON_CONFIG_CHANGE.lock().unwrap();
+              *current = !*current;
+              let new_value = *current;
+              drop(current); // Release the lock early
+
+              save_auto_restart_setting(app, new_value);
+              println!("Toggled auto-restart on config change to: {}", new_value);
+
+              refresh_tray_menu(app);
             }
             "quit" => {
               cleanup_and_exit_with_app(app);
@@ -844,7 +1538,11 @@ pub fn run() {
             api.prevent_close();
             if let Some(w) = app_handle2.get_webview_window("main") {
               let _ = w.hide();
+              if *MINIMIZE_TO_TRAY.lock().unwrap() {
+                set_dock_visible(&app_handle2, false);
+              }
             }
+            refresh_tray_menu(&app_handle2);
           }
         });
       }
@@ -857,18 +1555,35 @@ pub fn run() {
       // Start server automatically and redirect when ready
       let cfg = app_config(&app_handle);
       let app_handle3 = app_handle.clone();
+      let cfg_supervisor = cfg.clone();
+      let app_handle_supervisor = app_handle.clone();
+      let cfg_watcher = cfg.clone();
+      let app_handle_watcher = app_handle.clone();
       tauri::async_runtime::spawn(async move {
+        set_server_state(&app_handle3, ServerState::Starting);
         let _ = start_server(&app_handle3, &cfg);
         if wait_until_ready(&cfg.args, Duration::from_secs(SERVER_READY_TIMEOUT_SECS)).await {
+          set_server_state(&app_handle3, ServerState::Running);
           redirect_to_server(&app_handle3, &cfg);
+        } else {
+          set_server_state(&app_handle3, ServerState::Failed);
         }
       });
 
+      // Watch the child after it comes up and auto-restart it (with backoff) if it
+      // exits unexpectedly or stops responding over HTTP, so a crashed or wedged
+      // backend doesn't just leave the window dead.
+      spawn_supervisor(app_handle_supervisor, cfg_supervisor);
+
+      // Watch the config directory so edits can trigger an auto-reload without the
+      // user having to remember to hit "Restart Server".
+      spawn_config_watcher(app_handle_watcher, cfg_watcher);
+
       Ok(())
     })
     .build(tauri::generate_context!())
     .expect("error while building tauri application")
-    .run(move |_app, event| {
+    .run(move |app, event| {
       match event {
         RunEvent::ExitRequested { .. } => {
           // Check if we should exit cleanly
@@ -881,15 +1596,16 @@ pub fn run() {
           *SHOULD_EXIT.lock().unwrap() = true;
 
           // Do cleanup in background thread to avoid UI freeze
-          std::thread::spawn(|| {
-            stop_server();
+          let app = app.clone();
+          std::thread::spawn(move || {
+            stop_server(&app);
             std::process::exit(0);
           });
         }
         RunEvent::Exit => {
           // Final cleanup on actual exit
           if !*SHOULD_EXIT.lock().unwrap() {
-            stop_server();
+            stop_server(app);
           }
         }
         _ => {}